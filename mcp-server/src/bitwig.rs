@@ -4,40 +4,105 @@
 //! Bitwig connects to us as a client (inverted from typical server model).
 
 use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::io::{ReadHalf, WriteHalf};
-use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{Mutex, RwLock};
-use tracing::{error, info, warn};
+use tokio::sync::{broadcast, oneshot, watch, Mutex, RwLock};
+use tokio::time::{timeout, Instant};
+use tracing::{debug, error, info, warn};
 
-use crate::protocol::{self, RpcRequest};
+use crate::codec::Codec;
+use crate::protocol::{self, Incoming, RpcNotification, RpcRequest, RpcResponse};
+use crate::transport::{Listener, Stream};
 
-/// A single Bitwig connection
+/// Default per-call timeout when `GROOVE_CALL_TIMEOUT` is unset or invalid.
+const DEFAULT_CALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Per-call timeout, read from `GROOVE_CALL_TIMEOUT` as whole seconds.
+///
+/// An unset, unparseable, or zero value falls back to [`DEFAULT_CALL_TIMEOUT`],
+/// mirroring the `GROOVE_TRANSPORT`/`GROOVE_CODEC` knobs that likewise degrade
+/// to their defaults on bad input.
+fn call_timeout_from_env() -> Duration {
+    match std::env::var("GROOVE_CALL_TIMEOUT")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+    {
+        Some(secs) if secs > 0 => Duration::from_secs(secs),
+        _ => DEFAULT_CALL_TIMEOUT,
+    }
+}
+
+/// Capacity of the per-manager event broadcast channel. Slow subscribers that
+/// fall this far behind receive `RecvError::Lagged` rather than stalling the
+/// reader task.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Map of in-flight request ids to the waiter expecting their response.
+type PendingMap = Mutex<HashMap<u64, oneshot::Sender<RpcResponse>>>;
+
+/// A single Bitwig connection.
+///
+/// The transport is a multiplexed RPC channel: a long-lived reader task owns
+/// the read half and routes each `RpcResponse` back to the `call` that is
+/// waiting on its id, so overlapping calls neither serialize nor mismatch.
 struct BitwigConnection {
-    reader: Mutex<ReadHalf<TcpStream>>,
-    writer: Mutex<WriteHalf<TcpStream>>,
-    request_id: Mutex<u64>,
+    writer: Mutex<WriteHalf<Stream>>,
+    request_id: AtomicU64,
+    pending: Arc<PendingMap>,
+    call_timeout: Duration,
+    codec: Codec,
 }
 
 impl BitwigConnection {
-    fn new(stream: TcpStream) -> Self {
+    /// Split `stream`, keeping the write side and handing back the read half.
+    ///
+    /// The caller spawns the reader task (see [`BitwigManager::connect`]) so it
+    /// can carry the connection's epoch and notify the manager when the link
+    /// dies.
+    fn new(stream: Stream, call_timeout: Duration, codec: Codec) -> (Self, ReadHalf<Stream>) {
         let (reader, writer) = tokio::io::split(stream);
-        Self {
-            reader: Mutex::new(reader),
+        let pending: Arc<PendingMap> = Arc::new(Mutex::new(HashMap::new()));
+
+        let conn = Self {
             writer: Mutex::new(writer),
-            request_id: Mutex::new(0),
-        }
+            request_id: AtomicU64::new(0),
+            pending,
+            call_timeout,
+            codec,
+        };
+        (conn, reader)
+    }
+
+    /// Fail every outstanding call, waking each waiter with a closed error.
+    ///
+    /// Dropping each `oneshot::Sender` surfaces "connection closed" at the
+    /// matching `call`, so a link that dies between request and response never
+    /// leaves a caller hanging until its timeout.
+    async fn fail_pending(&self) {
+        self.pending.lock().await.clear();
     }
 
-    async fn next_id(&self) -> u64 {
-        let mut id = self.request_id.lock().await;
-        *id += 1;
-        *id
+    fn next_id(&self) -> u64 {
+        self.request_id.fetch_add(1, Ordering::Relaxed) + 1
     }
 
-    /// Send a request to Bitwig and wait for response
-    async fn call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
-        let id = self.next_id().await;
+    /// Send a request to Bitwig and wait for the matching response.
+    ///
+    /// `manager` and `epoch` let a timed-out call tear its own link down: a
+    /// half-open socket leaves the reader blocked in `read_exact` with no EOF
+    /// to observe, so without this the link would report `connected` forever
+    /// and every later call would wait out the full timeout.
+    async fn call(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        manager: &BitwigManager,
+        epoch: u64,
+    ) -> Result<serde_json::Value> {
+        let id = self.next_id();
 
         let request = RpcRequest {
             jsonrpc: "2.0".to_string(),
@@ -46,16 +111,36 @@ impl BitwigConnection {
             id: serde_json::json!(id),
         };
 
-        // Send request
+        // Register a waiter before writing so a fast response can't race us.
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id, tx);
+
+        // Send the framed request through the dedicated writer mutex.
         {
             let mut writer = self.writer.lock().await;
-            protocol::write_request(&mut *writer, &request).await?;
+            if let Err(e) = protocol::write_request(&mut *writer, &request, self.codec).await {
+                self.pending.lock().await.remove(&id);
+                return Err(e);
+            }
         }
 
-        // Read response
-        let response = {
-            let mut reader = self.reader.lock().await;
-            protocol::read_response(&mut *reader).await?
+        // Await the response, bounded by the per-call timeout.
+        let response = match timeout(self.call_timeout, rx).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(_)) => return Err(anyhow!("Bitwig connection closed before response")),
+            Err(_) => {
+                self.pending.lock().await.remove(&id);
+                // The socket is likely half-open: the reader will never see
+                // EOF, so tear the link down here rather than leaving it stuck
+                // reporting `connected`. Idempotent via the epoch if the reader
+                // happened to win the race.
+                manager.handle_disconnect(epoch).await;
+                return Err(anyhow!(
+                    "RPC call '{}' timed out after {:?}; tearing link down",
+                    method,
+                    self.call_timeout
+                ));
+            }
         };
 
         // Check for error
@@ -69,72 +154,526 @@ impl BitwigConnection {
     }
 }
 
+/// Route a response to the `call` awaiting its id.
+async fn dispatch_response(pending: &PendingMap, response: RpcResponse) {
+    let Some(id) = response.id.as_u64() else {
+        warn!("Ignoring response with non-numeric id: {:?}", response.id);
+        return;
+    };
+
+    match pending.lock().await.remove(&id) {
+        Some(tx) => {
+            let _ = tx.send(response);
+        }
+        None => debug!("No pending request for response id {}", id),
+    }
+}
+
+/// Long-lived task that owns the read half and demultiplexes inbound frames.
+///
+/// Responses are routed to their waiting `call`; id-less notifications are
+/// forwarded to the manager's broadcast channel for any subscribers. Every
+/// successfully read frame refreshes the link's last-seen time, so disconnect
+/// detection rides on real traffic rather than a ping the Bitwig extension may
+/// not implement. When the socket errors or reaches EOF the task tells the
+/// manager to tear the link down (which fails every pending waiter), so a dead
+/// Bitwig no longer leaves `bitwig_status` reporting `connected` forever.
+async fn reader_task(
+    mut reader: ReadHalf<Stream>,
+    pending: Arc<PendingMap>,
+    events: broadcast::Sender<RpcNotification>,
+    codec: Codec,
+    manager: BitwigManager,
+    epoch: u64,
+) {
+    loop {
+        match protocol::read_incoming(&mut reader, codec).await {
+            Ok(incoming) => {
+                manager.mark_seen();
+                match incoming {
+                    Incoming::Response(response) => dispatch_response(&pending, response).await,
+                    Incoming::Notification(notification) => {
+                        debug!("Bitwig notification: {}", notification.method);
+                        // Err only means there are no subscribers; that is fine.
+                        let _ = events.send(notification);
+                    }
+                }
+            }
+            Err(e) => {
+                info!("Bitwig reader stopped: {}", e);
+                break;
+            }
+        }
+    }
+
+    manager.handle_disconnect(epoch).await;
+}
+
+/// Observable connectivity of the Bitwig link.
+///
+/// Bitwig dials us, so `Connecting` is the window between accepting a socket
+/// and the reader task going live; once frames flow we are `Connected`, and a
+/// reader EOF or transport error drops us back to `Disconnected`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Disconnected,
+    Connecting,
+    Connected,
+}
+
+impl std::fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ConnectionState::Disconnected => "disconnected",
+            ConnectionState::Connecting => "connecting",
+            ConnectionState::Connected => "connected",
+        };
+        f.write_str(label)
+    }
+}
+
+/// A point-in-time snapshot of the Bitwig link, surfaced by `bitwig_status`.
+#[derive(Debug, Clone)]
+pub struct ConnectionStatus {
+    pub state: ConnectionState,
+    /// Wall-clock time of the last successfully observed frame, if any.
+    pub last_seen: Option<SystemTime>,
+    /// Consecutive drops since the link was last healthy.
+    pub reconnect_attempts: u32,
+    /// Suggested delay before the next status poll / reconnect window.
+    pub retry_after: Duration,
+}
+
+/// Base delay for the reconnect/status backoff.
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+
+/// Ceiling for the reconnect/status backoff.
+const BACKOFF_CAP: Duration = Duration::from_secs(30);
+
+/// Exponential backoff with jitter used for reconnect-attempt accounting.
+///
+/// `delay = min(cap, base * 2^attempt)` scaled by a random factor in
+/// `[0.5, 1.0)`, which decorrelates retry storms across clients.
+fn backoff_delay(attempt: u32) -> Duration {
+    let exp = BACKOFF_BASE.saturating_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX));
+    let capped = exp.min(BACKOFF_CAP);
+    capped.mul_f64(0.5 + 0.5 * jitter_unit())
+}
+
+/// A cheap, dependency-free jitter sample in `[0.0, 1.0)`.
+///
+/// Seeds an xorshift from the current sub-second clock — we only need it to
+/// spread retries, not for anything cryptographic.
+fn jitter_unit() -> f64 {
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let mut x = seed.wrapping_mul(2_654_435_761).wrapping_add(1);
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    f64::from(x) / (f64::from(u32::MAX) + 1.0)
+}
+
+/// Current wall-clock time as Unix milliseconds, or `0` if the clock predates
+/// the epoch (which only a badly misconfigured host could produce).
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Event categories named in a subscribe/unsubscribe request.
+///
+/// Accepts `{ "categories": ["transport", "track"] }`; an absent, non-array, or
+/// empty list defaults to `["all"]`, matching the broad subscription the MCP
+/// `bitwig_subscribe` tool requests.
+fn subscription_categories(params: &serde_json::Value) -> Vec<String> {
+    params
+        .get("categories")
+        .and_then(|c| c.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect::<Vec<_>>()
+        })
+        .filter(|cats| !cats.is_empty())
+        .unwrap_or_else(|| vec!["all".to_string()])
+}
+
+/// The active Bitwig link and its observable state, guarded as a unit so
+/// connectivity reporting can never disagree with the live connection.
+struct Link {
+    conn: Option<Arc<BitwigConnection>>,
+    /// Reader task for the live connection, aborted on teardown so closing the
+    /// link drops its read half and the socket actually closes — otherwise a
+    /// teardown that only dropped the write half would leave the reader parked
+    /// in `read_exact`, the socket half-open, and a slow-but-alive peer with no
+    /// EOF to prompt a redial.
+    reader_task: Option<tokio::task::JoinHandle<()>>,
+    /// Bumped on every successful connect; a stale reader task carries an older
+    /// epoch and so cannot tear down a link that already replaced it.
+    epoch: u64,
+    state: ConnectionState,
+    reconnect_attempts: u32,
+    /// Backoff sampled once per drop so repeated status polls stay stable.
+    retry_after: Duration,
+}
+
 /// Manager for Bitwig connections
 #[derive(Clone)]
 pub struct BitwigManager {
-    connection: Arc<RwLock<Option<Arc<BitwigConnection>>>>,
+    link: Arc<RwLock<Link>>,
+    /// Unix-millis of the last frame read, updated lock-free on the demux hot
+    /// path; `0` means no frame has ever been seen.
+    last_seen: Arc<AtomicU64>,
+    call_timeout: Duration,
+    events: broadcast::Sender<RpcNotification>,
+    /// Active subscriber count per event category. All clients share the one
+    /// broadcast channel, so the `subscribe`/`unsubscribe` control calls are
+    /// relayed to the extension only on 0→1 and 1→0 transitions.
+    sub_refs: Arc<Mutex<HashMap<String, u32>>>,
 }
 
 impl BitwigManager {
     pub fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
         Self {
-            connection: Arc::new(RwLock::new(None)),
+            link: Arc::new(RwLock::new(Link {
+                conn: None,
+                reader_task: None,
+                epoch: 0,
+                state: ConnectionState::Disconnected,
+                reconnect_attempts: 0,
+                retry_after: Duration::ZERO,
+            })),
+            last_seen: Arc::new(AtomicU64::new(0)),
+            call_timeout: call_timeout_from_env(),
+            events,
+            sub_refs: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Subscribe to Bitwig-pushed notifications.
+    ///
+    /// The returned receiver yields every notification forwarded by the reader
+    /// task from the moment of subscription. Use the `subscribe` control call
+    /// to tell the Bitwig extension which event categories to actually emit.
+    pub fn subscribe(&self) -> broadcast::Receiver<RpcNotification> {
+        self.events.subscribe()
+    }
+
+    /// Register interest in the given event categories for one subscriber.
+    ///
+    /// Refcounts each category and relays `subscribe` to the extension only for
+    /// those going from zero to one active subscriber, so a second client
+    /// subscribing to an already-live category issues no redundant control call
+    /// and — crucially — one client's [`unsubscribe_categories`] can never tell
+    /// Bitwig to stop emitting events another client is still streaming.
+    pub async fn subscribe_categories(&self, params: &serde_json::Value) -> Result<()> {
+        let categories = subscription_categories(params);
+        let newly = {
+            let mut refs = self.sub_refs.lock().await;
+            let mut newly = Vec::new();
+            for category in &categories {
+                let count = refs.entry(category.clone()).or_insert(0);
+                *count += 1;
+                if *count == 1 {
+                    newly.push(category.clone());
+                }
+            }
+            newly
+        };
+
+        if newly.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(e) = self
+            .call("subscribe", serde_json::json!({ "categories": newly }))
+            .await
+        {
+            // Undo the counts we just bumped: a transient failure must not wedge
+            // these categories at a non-zero refcount, which would make every
+            // later subscribe see `newly` empty and never re-send the control
+            // call, silently starving the events.
+            let mut refs = self.sub_refs.lock().await;
+            for category in &categories {
+                if let Some(count) = refs.get_mut(category) {
+                    *count = count.saturating_sub(1);
+                    if *count == 0 {
+                        refs.remove(category);
+                    }
+                }
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Release one subscriber's interest in the given event categories.
+    ///
+    /// Relays `unsubscribe` to the extension only for categories whose refcount
+    /// falls back to zero, so emission stops exactly when the last subscriber
+    /// leaves and not before.
+    pub async fn unsubscribe_categories(&self, params: &serde_json::Value) {
+        let freed = {
+            let mut refs = self.sub_refs.lock().await;
+            let mut freed = Vec::new();
+            for category in subscription_categories(params) {
+                if let Some(count) = refs.get_mut(&category) {
+                    *count = count.saturating_sub(1);
+                    if *count == 0 {
+                        refs.remove(&category);
+                        freed.push(category);
+                    }
+                }
+            }
+            freed
+        };
+
+        if freed.is_empty() {
+            return;
+        }
+        let _ = self
+            .call("unsubscribe", serde_json::json!({ "categories": freed }))
+            .await;
+    }
+
+    /// Mark that a socket has been accepted and is being brought up.
+    ///
+    /// Only advertises `Connecting` when no link is currently live; a redial
+    /// while an existing connection is still up must not regress the reported
+    /// state before [`connect`](Self::connect) swaps it.
+    async fn mark_connecting(&self) {
+        let mut link = self.link.write().await;
+        if link.conn.is_none() {
+            link.state = ConnectionState::Connecting;
         }
     }
 
-    /// Set the active Bitwig connection
-    async fn set_connection(&self, conn: BitwigConnection) {
-        let mut lock = self.connection.write().await;
-        *lock = Some(Arc::new(conn));
+    /// Fall back to `Disconnected` when bringing a socket up failed and no link
+    /// was established. Leaves an already-live connection untouched.
+    async fn reset_if_idle(&self) {
+        let mut link = self.link.write().await;
+        if link.conn.is_none() {
+            link.state = ConnectionState::Disconnected;
+        }
+    }
+
+    /// Adopt a freshly-negotiated stream as the active connection.
+    ///
+    /// Supersedes any previous link (its reader carries an older epoch and
+    /// becomes a no-op), resets the reconnect accounting, and spawns the reader
+    /// task for the new epoch.
+    async fn connect(&self, stream: Stream, codec: Codec) {
+        let (conn, reader) = BitwigConnection::new(stream, self.call_timeout, codec);
+        let conn = Arc::new(conn);
+
+        let (superseded_conn, superseded_task) = {
+            let mut link = self.link.write().await;
+            link.epoch += 1;
+            // Spawn the reader under the lock so the epoch, connection, and
+            // reader handle are always installed as one consistent set.
+            let task = tokio::spawn(reader_task(
+                reader,
+                conn.pending.clone(),
+                self.events.clone(),
+                codec,
+                self.clone(),
+                link.epoch,
+            ));
+            let superseded_conn = link.conn.replace(conn.clone());
+            let superseded_task = link.reader_task.replace(task);
+            link.state = ConnectionState::Connected;
+            link.reconnect_attempts = 0;
+            link.retry_after = Duration::ZERO;
+            (superseded_conn, superseded_task)
+        };
+        self.last_seen.store(now_millis(), Ordering::Relaxed);
+
+        // A redial that arrives before the old socket's reader noticed its EOF
+        // would otherwise leave those callers waiting for their full timeout.
+        if let Some(old) = superseded_conn {
+            old.fail_pending().await;
+        }
+        // Stop the old reader so its read half drops and the stale socket closes.
+        if let Some(old) = superseded_task {
+            old.abort();
+        }
         info!("Bitwig connection established");
+
+        // Replay the categories current subscribers still hold refs for. The
+        // refcounts outlive a dropped link, but the freshly connected Bitwig
+        // knows nothing of them, so without replaying the control call a redial
+        // would leave every subscriber silently starved of events. Spawned off
+        // the accept path so a slow ack can't stall acceptance of the next
+        // connection for up to the call timeout.
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let active = {
+                let refs = manager.sub_refs.lock().await;
+                refs.keys().cloned().collect::<Vec<_>>()
+            };
+            if active.is_empty() {
+                return;
+            }
+            if let Err(e) = manager
+                .call("subscribe", serde_json::json!({ "categories": active }))
+                .await
+            {
+                warn!("Failed to replay subscriptions after reconnect: {}", e);
+            }
+        });
     }
 
-    /// Clear the active connection
-    async fn clear_connection(&self) {
-        let mut lock = self.connection.write().await;
-        *lock = None;
+    /// Tear down the link identified by `epoch`, if it is still current.
+    ///
+    /// Called by the reader task on EOF/error, and by a call that times out on a
+    /// half-open socket. Drops the connection and its reader task (closing the
+    /// socket so the peer sees EOF and can redial), fails every outstanding
+    /// request, and steps the reconnect counter so `bitwig_status` reflects
+    /// reality immediately.
+    async fn handle_disconnect(&self, epoch: u64) {
+        let (dropped, task) = {
+            let mut link = self.link.write().await;
+            // Key off the live connection, not the reported state: the state
+            // field is advisory, while epoch + presence keep this idempotent if
+            // it is ever called twice for the same connection.
+            if link.epoch != epoch || link.conn.is_none() {
+                return;
+            }
+            link.state = ConnectionState::Disconnected;
+            link.reconnect_attempts = link.reconnect_attempts.saturating_add(1);
+            link.retry_after = backoff_delay(link.reconnect_attempts);
+            (link.conn.take(), link.reader_task.take())
+        };
+
+        if let Some(conn) = dropped {
+            conn.fail_pending().await;
+        }
+        // Abort the reader so its read half drops and the socket fully closes.
+        // When the reader task is itself the caller (EOF path) this aborts the
+        // already-finishing task, which is a harmless no-op.
+        if let Some(task) = task {
+            task.abort();
+        }
         info!("Bitwig connection cleared");
     }
 
-    /// Check if Bitwig is connected
-    pub async fn is_connected(&self) -> bool {
-        self.connection.read().await.is_some()
+    /// Refresh the last-seen time from the demux hot path.
+    ///
+    /// Lock-free by design: a superseded reader writing a slightly stale
+    /// timestamp is harmless because the newer connection's reads overwrite it.
+    fn mark_seen(&self) {
+        self.last_seen.store(now_millis(), Ordering::Relaxed);
+    }
+
+    /// Snapshot the current connectivity for status reporting.
+    pub async fn status(&self) -> ConnectionStatus {
+        let link = self.link.read().await;
+        let last_seen = match self.last_seen.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(UNIX_EPOCH + Duration::from_millis(ms)),
+        };
+        ConnectionStatus {
+            state: link.state,
+            last_seen,
+            reconnect_attempts: link.reconnect_attempts,
+            retry_after: link.retry_after,
+        }
+    }
+
+    /// Number of calls currently awaiting a response.
+    pub async fn pending_requests(&self) -> usize {
+        match &self.link.read().await.conn {
+            Some(conn) => conn.pending.lock().await.len(),
+            None => 0,
+        }
+    }
+
+    /// Wait for in-flight calls to complete, up to `grace`.
+    ///
+    /// The reader task keeps running throughout, so responses that arrive
+    /// during the grace period are still delivered to their waiters before we
+    /// close the write halves and exit.
+    pub async fn drain(&self, grace: Duration) {
+        let deadline = Instant::now() + grace;
+        loop {
+            let pending = self.pending_requests().await;
+            if pending == 0 {
+                return;
+            }
+            if Instant::now() >= deadline {
+                warn!(
+                    "Grace period elapsed with {} request(s) still in flight",
+                    pending
+                );
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
     }
 
     /// Call a method on Bitwig
     pub async fn call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
-        let conn = {
-            let lock = self.connection.read().await;
-            lock.clone()
+        let (conn, epoch) = {
+            let link = self.link.read().await;
+            (link.conn.clone(), link.epoch)
         };
 
         match conn {
-            Some(conn) => conn.call(method, params).await,
+            Some(conn) => conn.call(method, params, self, epoch).await,
             None => Err(anyhow!("Bitwig not connected")),
         }
     }
 }
 
 /// Listen for Bitwig connections
-pub async fn listen(port: u16, manager: BitwigManager) -> Result<()> {
-    let listener = TcpListener::bind(format!("127.0.0.1:{}", port)).await?;
-    info!("Bitwig listener started on port {}", port);
+///
+/// Stops accepting new connections once `shutdown` flips to `true`.
+pub async fn listen(
+    mut listener: Listener,
+    manager: BitwigManager,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<()> {
+    info!("Bitwig listener started");
 
     loop {
-        match listener.accept().await {
-            Ok((stream, addr)) => {
-                info!("Bitwig connected from {}", addr);
+        let accepted = tokio::select! {
+            _ = shutdown.changed() => {
+                info!("Bitwig listener shutting down");
+                return Ok(());
+            }
+            accepted = listener.accept() => accepted,
+        };
+
+        match accepted {
+            Ok(mut stream) => {
+                info!("Bitwig connected");
+                manager.mark_connecting().await;
 
-                // Set TCP_NODELAY for lower latency
+                // Set TCP_NODELAY for lower latency (no-op for IPC transports).
                 if let Err(e) = stream.set_nodelay(true) {
                     warn!("Failed to set TCP_NODELAY: {}", e);
                 }
 
-                let conn = BitwigConnection::new(stream);
-                manager.set_connection(conn).await;
+                // Agree on the wire codec before any framed RPC.
+                let codec = match protocol::negotiate_codec(&mut stream, Codec::from_env()).await {
+                    Ok(codec) => codec,
+                    Err(e) => {
+                        error!("Codec negotiation with Bitwig failed: {}", e);
+                        // Never reached `Connected`, so fall back to disconnected.
+                        manager.reset_if_idle().await;
+                        continue;
+                    }
+                };
 
-                // TODO: Handle disconnection detection
-                // For now, we just replace the connection on new connect
+                // Adopt the connection; the reader task it spawns surfaces a
+                // dead socket and tears the link back down.
+                manager.connect(stream, codec).await;
             }
             Err(e) => {
                 error!("Failed to accept Bitwig connection: {}", e);
@@ -142,3 +681,104 @@ pub async fn listen(port: u16, manager: BitwigManager) -> Result<()> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Responses route to the waiter matching their id, regardless of the order
+    /// they arrive in — the core guarantee of the multiplexed reader.
+    #[tokio::test]
+    async fn dispatch_response_routes_by_id() {
+        let pending: PendingMap = Mutex::new(HashMap::new());
+        let (tx1, rx1) = oneshot::channel();
+        let (tx2, rx2) = oneshot::channel();
+        pending.lock().await.insert(1, tx1);
+        pending.lock().await.insert(2, tx2);
+
+        // Arrive out of order: id 2 first, then id 1.
+        dispatch_response(
+            &pending,
+            RpcResponse::success(serde_json::json!(2), serde_json::json!("b")),
+        )
+        .await;
+        dispatch_response(
+            &pending,
+            RpcResponse::success(serde_json::json!(1), serde_json::json!("a")),
+        )
+        .await;
+
+        assert_eq!(rx1.await.unwrap().result, Some(serde_json::json!("a")));
+        assert_eq!(rx2.await.unwrap().result, Some(serde_json::json!("b")));
+        assert!(pending.lock().await.is_empty());
+    }
+
+    /// A response for an unknown id is dropped without disturbing other waiters.
+    #[tokio::test]
+    async fn dispatch_response_ignores_unknown_id() {
+        let pending: PendingMap = Mutex::new(HashMap::new());
+        let (tx, rx) = oneshot::channel();
+        pending.lock().await.insert(1, tx);
+
+        dispatch_response(
+            &pending,
+            RpcResponse::success(serde_json::json!(99), serde_json::json!("x")),
+        )
+        .await;
+        assert_eq!(pending.lock().await.len(), 1);
+
+        dispatch_response(
+            &pending,
+            RpcResponse::success(serde_json::json!(1), serde_json::json!("y")),
+        )
+        .await;
+        assert_eq!(rx.await.unwrap().result, Some(serde_json::json!("y")));
+    }
+
+    /// Categories default to `["all"]` unless the request narrows them.
+    #[test]
+    fn subscription_categories_defaults_to_all() {
+        assert_eq!(
+            subscription_categories(&serde_json::json!({})),
+            vec!["all".to_string()]
+        );
+        assert_eq!(
+            subscription_categories(&serde_json::json!({ "categories": [] })),
+            vec!["all".to_string()]
+        );
+        assert_eq!(
+            subscription_categories(&serde_json::json!({ "categories": ["transport", "track"] })),
+            vec!["transport".to_string(), "track".to_string()]
+        );
+    }
+
+    /// A fresh manager reports no live link and no last-seen timestamp.
+    #[tokio::test]
+    async fn fresh_manager_is_disconnected() {
+        let manager = BitwigManager::new();
+        let status = manager.status().await;
+        assert_eq!(status.state, ConnectionState::Disconnected);
+        assert!(status.last_seen.is_none());
+    }
+
+    /// Backoff stays within `[0.5*cap, cap]` and never exceeds the ceiling even
+    /// for large attempt counts (the shift must not overflow).
+    #[test]
+    fn backoff_delay_respects_cap() {
+        for attempt in 0..64 {
+            let delay = backoff_delay(attempt);
+            assert!(delay <= BACKOFF_CAP, "attempt {} exceeded cap", attempt);
+        }
+        // Far past saturation the delay sits in the jittered top half of the cap.
+        let saturated = backoff_delay(40);
+        assert!(saturated >= BACKOFF_CAP.mul_f64(0.5));
+    }
+
+    /// The base delay for the first retry is bounded by `base` (times jitter).
+    #[test]
+    fn backoff_delay_first_attempt_near_base() {
+        let delay = backoff_delay(0);
+        assert!(delay <= BACKOFF_BASE);
+        assert!(delay >= BACKOFF_BASE.mul_f64(0.5));
+    }
+}
@@ -0,0 +1,89 @@
+//! Pluggable wire codecs
+//!
+//! The length-prefixed framing in [`crate::protocol`] is codec-agnostic: only
+//! the payload encoding varies. JSON is always available and is the default;
+//! MessagePack is compiled in behind the `serialize_rmp` cargo feature and
+//! meaningfully cuts latency and bandwidth on the big track/clip list and dump
+//! responses.
+//!
+//! Only self-describing codecs belong here: the frame structs embed
+//! [`serde_json::Value`] (`params`, `result`, `id`), which deserializes through
+//! `deserialize_any` and therefore cannot be carried by positional formats like
+//! bincode or postcard — those reject `deserialize_any` at runtime, so they are
+//! deliberately absent.
+
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Wire codec for frame payloads.
+///
+/// The codec is negotiated once at connection setup via a one-byte tag (see
+/// [`Codec::tag`]) exchanged in the clear. Both ends only upgrade away from JSON
+/// when they independently prefer the *same* codec; anything else falls back to
+/// [`Codec::Json`], which is always compiled in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    /// `serde_json` — always available, the default and handshake codec.
+    Json,
+    #[cfg(feature = "serialize_rmp")]
+    MessagePack,
+}
+
+impl Codec {
+    /// The codec new connections start with; JSON stays the default.
+    pub const DEFAULT: Codec = Codec::Json;
+
+    /// The preferred codec to offer during negotiation, from `GROOVE_CODEC`.
+    ///
+    /// Recognises `json` and `msgpack`/`messagepack`; an unset, unknown, or
+    /// not-compiled-in value falls back to [`Codec::DEFAULT`] so a misconfigured
+    /// environment can never break the handshake.
+    pub fn from_env() -> Codec {
+        match std::env::var("GROOVE_CODEC").ok().as_deref() {
+            #[cfg(feature = "serialize_rmp")]
+            Some("msgpack") | Some("messagepack") => Codec::MessagePack,
+            _ => Codec::DEFAULT,
+        }
+    }
+
+    /// One-byte wire tag identifying this codec during negotiation.
+    pub fn tag(self) -> u8 {
+        match self {
+            Codec::Json => 0,
+            #[cfg(feature = "serialize_rmp")]
+            Codec::MessagePack => 1,
+        }
+    }
+
+    /// Resolve a negotiated tag back to a codec.
+    ///
+    /// Tags for codecs that were not compiled in are rejected so the peer can
+    /// fall back to JSON rather than silently mis-decoding every frame.
+    pub fn from_tag(tag: u8) -> Result<Codec> {
+        match tag {
+            0 => Ok(Codec::Json),
+            #[cfg(feature = "serialize_rmp")]
+            1 => Ok(Codec::MessagePack),
+            other => Err(anyhow!("unsupported or disabled codec tag: {}", other)),
+        }
+    }
+
+    /// Encode a frame body with this codec.
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            Codec::Json => Ok(serde_json::to_vec(value)?),
+            #[cfg(feature = "serialize_rmp")]
+            Codec::MessagePack => Ok(rmp_serde::to_vec_named(value)?),
+        }
+    }
+
+    /// Decode a frame body with this codec.
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T> {
+        match self {
+            Codec::Json => Ok(serde_json::from_slice(bytes)?),
+            #[cfg(feature = "serialize_rmp")]
+            Codec::MessagePack => Ok(rmp_serde::from_slice(bytes)?),
+        }
+    }
+}
@@ -8,16 +8,72 @@
 //! - CLI talks to us via JSON-RPC over TCP on port 8418
 
 mod bitwig;
+mod codec;
 mod protocol;
 mod server;
+mod transport;
+
+use std::time::Duration;
 
 use anyhow::Result;
+use tokio::sync::watch;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use crate::transport::{Endpoint, Listener, Transport};
+
 const BITWIG_PORT: u16 = 8417;
 const CLI_PORT: u16 = 8418;
 
+/// How long to wait for in-flight requests to drain on shutdown.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Resolve the endpoints for both listeners from the selected transport.
+fn endpoints(transport: Transport) -> (Endpoint, Endpoint) {
+    match transport {
+        Transport::Tcp => (Endpoint::Tcp(BITWIG_PORT), Endpoint::Tcp(CLI_PORT)),
+        Transport::Ipc => {
+            let (bitwig, cli) = ipc_addrs();
+            (Endpoint::Ipc(bitwig), Endpoint::Ipc(cli))
+        }
+    }
+}
+
+/// Local-IPC addresses for the Bitwig and CLI endpoints (`GROOVE_TRANSPORT=ipc`).
+///
+/// Both sockets sit under a single owner-only `0700` directory the listener
+/// confines before anyone else could connect (see
+/// [`transport::Listener::bind`]). The directory is per-user — under
+/// `XDG_RUNTIME_DIR` when set (the conventional per-user runtime dir),
+/// otherwise a `$USER`-tagged path under /tmp — so two accounts on one host
+/// never collide on a shared name a third party could squat. Only this leaf
+/// directory is guaranteed `0700`; a base path the listener has to materialize
+/// inherits the process umask.
+#[cfg(unix)]
+fn ipc_addrs() -> (String, String) {
+    let dir = match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(base) => std::path::PathBuf::from(base).join("groove-link"),
+        None => {
+            let who = std::env::var("USER")
+                .or_else(|_| std::env::var("LOGNAME"))
+                .unwrap_or_else(|_| "nobody".into());
+            std::path::PathBuf::from(format!("/tmp/groove-link-{}", who))
+        }
+    };
+    (
+        dir.join("bitwig.sock").to_string_lossy().into_owned(),
+        dir.join("cli.sock").to_string_lossy().into_owned(),
+    )
+}
+
+#[cfg(windows)]
+fn ipc_addrs() -> (String, String) {
+    (
+        r"\\.\pipe\groove-bitwig".to_string(),
+        r"\\.\pipe\groove-cli".to_string(),
+    )
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
@@ -28,28 +84,41 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
         .init();
 
+    let transport = Transport::from_env();
+    let (bitwig_endpoint, cli_endpoint) = endpoints(transport);
+
     info!("Groove MCP Server starting...");
-    info!("Bitwig port: {}", BITWIG_PORT);
-    info!("CLI port: {}", CLI_PORT);
+    info!("Transport: {:?}", transport);
+    info!("Bitwig endpoint: {:?}", bitwig_endpoint);
+    info!("CLI endpoint: {:?}", cli_endpoint);
 
     // Create the shared Bitwig connection manager
     let bitwig_manager = bitwig::BitwigManager::new();
 
-    // Start the Bitwig TCP listener
+    // Bind both listeners before spawning so a bind failure aborts startup.
+    let bitwig_listener = Listener::bind(&bitwig_endpoint).await?;
+    let cli_listener = Listener::bind(&cli_endpoint).await?;
+
+    // Shutdown signal broadcast to both accept loops.
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    // Start the Bitwig listener
     let bitwig_handle = {
         let manager = bitwig_manager.clone();
+        let shutdown = shutdown_rx.clone();
         tokio::spawn(async move {
-            if let Err(e) = bitwig::listen(BITWIG_PORT, manager).await {
+            if let Err(e) = bitwig::listen(bitwig_listener, manager, shutdown).await {
                 tracing::error!("Bitwig listener error: {}", e);
             }
         })
     };
 
-    // Start the CLI TCP listener
+    // Start the CLI listener
     let cli_handle = {
         let manager = bitwig_manager.clone();
+        let shutdown = shutdown_rx.clone();
         tokio::spawn(async move {
-            if let Err(e) = server::cli_listener(CLI_PORT, manager).await {
+            if let Err(e) = server::cli_listener(cli_listener, manager, shutdown).await {
                 tracing::error!("CLI listener error: {}", e);
             }
         })
@@ -68,11 +137,45 @@ async fn main() -> Result<()> {
         });
     }
 
-    // Wait for TCP listeners
+    // Run until a shutdown signal arrives or a listener stops unexpectedly.
     tokio::select! {
+        _ = shutdown_signal() => info!("Shutdown signal received"),
         _ = bitwig_handle => info!("Bitwig listener stopped"),
         _ = cli_handle => info!("CLI listener stopped"),
     }
 
+    // Tell the accept loops to stop taking new connections.
+    let _ = shutdown_tx.send(true);
+
+    // Give in-flight calls a bounded window to complete before we exit.
+    info!(
+        "Draining in-flight requests (up to {:?})",
+        SHUTDOWN_GRACE_PERIOD
+    );
+    bitwig_manager.drain(SHUTDOWN_GRACE_PERIOD).await;
+
+    info!("Groove MCP Server stopped");
     Ok(())
 }
+
+/// Resolve when the process receives an OS termination signal.
+///
+/// Handles SIGINT and SIGTERM on Unix and Ctrl-C on Windows.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut sigint = signal(SignalKind::interrupt()).expect("install SIGINT handler");
+        let mut sigterm = signal(SignalKind::terminate()).expect("install SIGTERM handler");
+        tokio::select! {
+            _ = sigint.recv() => info!("Received SIGINT"),
+            _ = sigterm.recv() => info!("Received SIGTERM"),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        info!("Received Ctrl-C");
+    }
+}
@@ -12,6 +12,8 @@ use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+use crate::codec::Codec;
+
 /// JSON-RPC 2.0 Request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RpcRequest {
@@ -22,6 +24,18 @@ pub struct RpcRequest {
     pub id: serde_json::Value,
 }
 
+/// JSON-RPC 2.0 Notification
+///
+/// A request-shaped frame with no `id`. Bitwig pushes these to notify us of
+/// state changes (transport, track, parameter) we did not ask for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcNotification {
+    pub jsonrpc: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
 /// JSON-RPC 2.0 Response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RpcResponse {
@@ -117,9 +131,12 @@ pub async fn write_frame<W: AsyncWriteExt + Unpin>(writer: &mut W, payload: &[u8
 }
 
 /// Read a JSON-RPC request from a stream
-pub async fn read_request<R: AsyncReadExt + Unpin>(reader: &mut R) -> Result<RpcRequest> {
+pub async fn read_request<R: AsyncReadExt + Unpin>(
+    reader: &mut R,
+    codec: Codec,
+) -> Result<RpcRequest> {
     let payload = read_frame(reader).await?;
-    let request: RpcRequest = serde_json::from_slice(&payload)?;
+    let request: RpcRequest = codec.decode(&payload)?;
     tracing::debug!("Received request: {:?}", request);
     Ok(request)
 }
@@ -128,26 +145,237 @@ pub async fn read_request<R: AsyncReadExt + Unpin>(reader: &mut R) -> Result<Rpc
 pub async fn write_response<W: AsyncWriteExt + Unpin>(
     writer: &mut W,
     response: &RpcResponse,
+    codec: Codec,
 ) -> Result<()> {
-    let payload = serde_json::to_vec(response)?;
+    let payload = codec.encode(response)?;
     tracing::debug!("Sending response: {:?}", response);
     write_frame(writer, &payload).await
 }
 
-/// Read a JSON-RPC response from a stream
-pub async fn read_response<R: AsyncReadExt + Unpin>(reader: &mut R) -> Result<RpcResponse> {
-    let payload = read_frame(reader).await?;
-    let response: RpcResponse = serde_json::from_slice(&payload)?;
-    tracing::debug!("Received response: {:?}", response);
-    Ok(response)
-}
-
 /// Write a JSON-RPC request to a stream
 pub async fn write_request<W: AsyncWriteExt + Unpin>(
     writer: &mut W,
     request: &RpcRequest,
+    codec: Codec,
 ) -> Result<()> {
-    let payload = serde_json::to_vec(request)?;
+    let payload = codec.encode(request)?;
     tracing::debug!("Sending request: {:?}", request);
     write_frame(writer, &payload).await
 }
+
+/// An inbound frame from Bitwig.
+///
+/// Bitwig multiplexes two kinds of frame onto the same socket: responses to
+/// our own `call`s (which carry an `id`) and id-less notifications it pushes
+/// on its own initiative.
+#[derive(Debug, Clone)]
+pub enum Incoming {
+    Response(RpcResponse),
+    Notification(RpcNotification),
+}
+
+/// A superset frame that can hold either inbound shape.
+///
+/// Decoding once into this envelope and branching on which fields are present
+/// discriminates responses from notifications *structurally*, rather than by
+/// probing whether an overlapping struct happens to fail to decode — which is
+/// only reliable for self-describing codecs.
+#[derive(Debug, Deserialize)]
+struct IncomingEnvelope {
+    #[serde(default)]
+    jsonrpc: String,
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    params: serde_json::Value,
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+    #[serde(default)]
+    result: Option<serde_json::Value>,
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+/// Read one inbound frame, distinguishing a response from a notification.
+///
+/// A notification is request-shaped (`method`, no `id`); a response carries a
+/// `result` or `error` and an `id`. The `method` field is present only on
+/// notifications, so its presence is the discriminator — decided from a single
+/// decode of [`IncomingEnvelope`] so the split does not depend on decode-failure
+/// behaviour that varies across codecs.
+pub async fn read_incoming<R: AsyncReadExt + Unpin>(
+    reader: &mut R,
+    codec: Codec,
+) -> Result<Incoming> {
+    let payload = read_frame(reader).await?;
+    let envelope: IncomingEnvelope = codec.decode(&payload)?;
+
+    if let Some(method) = envelope.method {
+        let notification = RpcNotification {
+            jsonrpc: envelope.jsonrpc,
+            method,
+            params: envelope.params,
+        };
+        tracing::debug!("Received notification: {:?}", notification);
+        return Ok(Incoming::Notification(notification));
+    }
+
+    // A response must carry an id; a frame with neither `method` nor `id` is
+    // malformed and fails the read, which surfaces as a disconnect rather than
+    // a silently dropped Null-id response.
+    let Some(id) = envelope.id else {
+        return Err(anyhow!("inbound frame has neither method nor id"));
+    };
+    let response = RpcResponse {
+        jsonrpc: envelope.jsonrpc,
+        result: envelope.result,
+        error: envelope.error,
+        id,
+    };
+    tracing::debug!("Received response: {:?}", response);
+    Ok(Incoming::Response(response))
+}
+
+/// Write a JSON-RPC notification to a stream
+pub async fn write_notification<W: AsyncWriteExt + Unpin>(
+    writer: &mut W,
+    notification: &RpcNotification,
+    codec: Codec,
+) -> Result<()> {
+    let payload = codec.encode(notification)?;
+    tracing::debug!("Sending notification: {:?}", notification);
+    write_frame(writer, &payload).await
+}
+
+/// Negotiate the wire codec for a freshly-accepted connection.
+///
+/// Each side writes the one-byte tag of its preferred codec and reads the
+/// peer's. Both ends compute the same result from the two tags: the shared
+/// codec is used only when both sides preferred it *and* we can honour it (it
+/// was compiled in); otherwise both fall back to JSON, which is always
+/// available. Symmetry is what makes this safe — a peer offering a codec we did
+/// not also offer never leaves the two ends disagreeing on the wire format. The
+/// tag bytes are exchanged in the clear before any codec-encoded frame, so the
+/// handshake itself needs no prior agreement.
+pub async fn negotiate_codec<S: AsyncReadExt + AsyncWriteExt + Unpin>(
+    stream: &mut S,
+    preferred: Codec,
+) -> Result<Codec> {
+    stream.write_all(&[preferred.tag()]).await?;
+    stream.flush().await?;
+
+    let mut tag = [0u8; 1];
+    stream.read_exact(&mut tag).await?;
+
+    // Adopt the peer's codec only if it matches our own preference; any
+    // mismatch (or a tag we cannot honour) deterministically resolves to JSON.
+    let agreed = match Codec::from_tag(tag[0]) {
+        Ok(peer) if peer == preferred => peer,
+        _ => Codec::Json,
+    };
+    tracing::info!("Negotiated wire codec: {:?}", agreed);
+    Ok(agreed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A frame carrying `method` and no `id` demuxes to a notification.
+    #[tokio::test]
+    async fn read_incoming_treats_idless_frame_as_notification() {
+        let (mut a, mut b) = tokio::io::duplex(1024);
+        let notification = RpcNotification {
+            jsonrpc: "2.0".to_string(),
+            method: "transport.play".to_string(),
+            params: serde_json::json!({"bpm": 120}),
+        };
+        write_notification(&mut a, &notification, Codec::Json)
+            .await
+            .unwrap();
+
+        match read_incoming(&mut b, Codec::Json).await.unwrap() {
+            Incoming::Notification(n) => {
+                assert_eq!(n.method, "transport.play");
+                assert_eq!(n.params, serde_json::json!({"bpm": 120}));
+            }
+            other => panic!("expected notification, got {:?}", other),
+        }
+    }
+
+    /// A frame carrying `result` and an `id` demuxes to a response.
+    #[tokio::test]
+    async fn read_incoming_treats_id_frame_as_response() {
+        let (mut a, mut b) = tokio::io::duplex(1024);
+        let response = RpcResponse::success(serde_json::json!(7), serde_json::json!({"ok": true}));
+        write_response(&mut a, &response, Codec::Json).await.unwrap();
+
+        match read_incoming(&mut b, Codec::Json).await.unwrap() {
+            Incoming::Response(r) => {
+                assert_eq!(r.id, serde_json::json!(7));
+                assert_eq!(r.result, Some(serde_json::json!({"ok": true})));
+            }
+            other => panic!("expected response, got {:?}", other),
+        }
+    }
+
+    /// A frame with neither `method` nor `id` is malformed and errors out.
+    #[tokio::test]
+    async fn read_incoming_rejects_frame_without_method_or_id() {
+        let (mut a, mut b) = tokio::io::duplex(1024);
+        write_frame(&mut a, &serde_json::to_vec(&serde_json::json!({"jsonrpc": "2.0"})).unwrap())
+            .await
+            .unwrap();
+        assert!(read_incoming(&mut b, Codec::Json).await.is_err());
+    }
+
+    /// An error response round-trips through the framing and demuxes back to a
+    /// response carrying its error.
+    #[tokio::test]
+    async fn response_round_trip_preserves_error() {
+        let (mut a, mut b) = tokio::io::duplex(1024);
+        let response = RpcResponse::error(serde_json::json!(3), -32603, "boom".to_string());
+        write_response(&mut a, &response, Codec::Json).await.unwrap();
+
+        match read_incoming(&mut b, Codec::Json).await.unwrap() {
+            Incoming::Response(read) => {
+                assert_eq!(read.id, serde_json::json!(3));
+                let error = read.error.expect("error present");
+                assert_eq!(error.code, -32603);
+                assert_eq!(error.message, "boom");
+            }
+            other => panic!("expected response, got {:?}", other),
+        }
+    }
+
+    /// Two peers that prefer the same codec agree on it.
+    #[tokio::test]
+    async fn negotiate_codec_agrees_when_both_prefer_json() {
+        let (mut a, mut b) = tokio::io::duplex(8);
+        let (ra, rb) = tokio::join!(
+            negotiate_codec(&mut a, Codec::Json),
+            negotiate_codec(&mut b, Codec::Json),
+        );
+        assert_eq!(ra.unwrap(), Codec::Json);
+        assert_eq!(rb.unwrap(), Codec::Json);
+    }
+
+    /// An unknown/disabled peer tag deterministically falls back to JSON.
+    #[tokio::test]
+    async fn negotiate_codec_falls_back_on_unknown_peer_tag() {
+        let (mut ours, mut peer) = tokio::io::duplex(8);
+
+        // The peer offers a tag we do not recognise.
+        let peer_side = async move {
+            peer.write_all(&[0xFF]).await.unwrap();
+            peer.flush().await.unwrap();
+            let mut tag = [0u8; 1];
+            peer.read_exact(&mut tag).await.unwrap();
+            tag[0]
+        };
+
+        let (agreed, our_tag) = tokio::join!(negotiate_codec(&mut ours, Codec::Json), peer_side);
+        assert_eq!(agreed.unwrap(), Codec::Json);
+        assert_eq!(our_tag, Codec::Json.tag());
+    }
+}
@@ -7,15 +7,15 @@ use anyhow::Result;
 use rmcp::{
     handler::server::tool::ToolRouter,
     handler::server::ServerHandler,
-    model::*,
     tool, tool_router, tool_handler,
     ServiceExt,
 };
-use tokio::net::TcpListener;
 use tracing::{error, info};
 
 use crate::bitwig::BitwigManager;
-use crate::protocol::{self, RpcResponse};
+use crate::codec::Codec;
+use crate::protocol::{self, RpcRequest, RpcResponse};
+use crate::transport::{Listener, Stream};
 
 /// MCP Server with Bitwig tools
 #[derive(Clone)]
@@ -61,11 +61,71 @@ impl GrooveMcpServer {
     /// Check if Bitwig is connected
     #[tool(description = "Check if Bitwig Studio is connected to the MCP server")]
     async fn bitwig_status(&self) -> String {
-        let connected = self.bitwig.is_connected().await;
-        format!("Bitwig connected: {}", connected)
+        let status = self.bitwig.status().await;
+        let last_seen = match status.last_seen {
+            Some(t) => match t.elapsed() {
+                Ok(age) => format!("{}s ago", age.as_secs()),
+                // Clock skew put last_seen in the future; report it as current.
+                Err(_) => "just now".to_string(),
+            },
+            None => "never".to_string(),
+        };
+        format!(
+            "Bitwig state: {}\nLast seen: {}\nReconnect attempts: {}\nRetry after: {:?}\n\
+             Liveness: call-driven (no active heartbeat). A call that times out \
+             tears the link down, so `state` reflects the last call's outcome; \
+             but while idle a silently dead socket reads `connected` until the \
+             next call, so weigh `state` against how long ago `last seen` was.",
+            status.state, last_seen, status.reconnect_attempts, status.retry_after
+        )
+    }
+
+    /// Subscribe to Bitwig events and collect those pushed over a short window
+    #[tool(
+        description = "Subscribe to Bitwig events (transport, track, parameter changes) \
+                       and return the notifications pushed over the next few seconds"
+    )]
+    async fn bitwig_subscribe(&self) -> String {
+        // Start receiving before telling Bitwig to emit, so nothing is missed.
+        let mut events = self.bitwig.subscribe();
+
+        let categories = serde_json::json!({ "categories": ["all"] });
+        if let Err(e) = self.bitwig.subscribe_categories(&categories).await {
+            return format!("Error: {}", e);
+        }
+
+        // MCP tool calls return a single value, so we drain whatever arrives
+        // within a bounded window rather than streaming indefinitely. Long-lived
+        // streaming is available over the CLI `bitwig_subscribe` method.
+        let mut collected = Vec::new();
+        let window = tokio::time::sleep(EVENT_COLLECTION_WINDOW);
+        tokio::pin!(window);
+
+        loop {
+            tokio::select! {
+                _ = &mut window => break,
+                event = events.recv() => match event {
+                    Ok(notification) => collected.push(serde_json::json!({
+                        "method": notification.method,
+                        "params": notification.params,
+                    })),
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::warn!("bitwig_subscribe lagged, dropped {} events", n);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                },
+            }
+        }
+
+        self.bitwig.unsubscribe_categories(&categories).await;
+
+        serde_json::to_string_pretty(&collected).unwrap_or_else(|_| "[]".to_string())
     }
 }
 
+/// How long `bitwig_subscribe` collects pushed events before returning.
+const EVENT_COLLECTION_WINDOW: std::time::Duration = std::time::Duration::from_secs(3);
+
 /// Run MCP server over stdio for Claude Code
 pub async fn run_mcp_stdio(bitwig: BitwigManager) -> Result<()> {
     info!("Starting MCP server over stdio");
@@ -88,15 +148,15 @@ pub async fn run_mcp_stdio(bitwig: BitwigManager) -> Result<()> {
 }
 
 /// Handle a single CLI connection
-async fn handle_cli_connection(
-    mut stream: tokio::net::TcpStream,
-    bitwig: BitwigManager,
-) -> Result<()> {
-    let (mut reader, mut writer) = stream.split();
+async fn handle_cli_connection(mut stream: Stream, bitwig: BitwigManager) -> Result<()> {
+    // Agree on the wire codec before splitting into read/write halves.
+    let codec = protocol::negotiate_codec(&mut stream, Codec::from_env()).await?;
+
+    let (mut reader, mut writer) = tokio::io::split(stream);
 
     loop {
         // Read request from CLI
-        let request = match protocol::read_request(&mut reader).await {
+        let request = match protocol::read_request(&mut reader, codec).await {
             Ok(req) => req,
             Err(e) => {
                 // Connection closed or error
@@ -105,6 +165,12 @@ async fn handle_cli_connection(
             }
         };
 
+        // Streaming mode: forward Bitwig notifications until the CLI disconnects.
+        if request.method == "bitwig_subscribe" {
+            stream_subscription(&mut reader, &mut writer, &bitwig, &request, codec).await;
+            break;
+        }
+
         // Dispatch to Bitwig
         let response = match bitwig.call(&request.method, request.params.clone()).await {
             Ok(result) => RpcResponse::success(request.id, result),
@@ -112,7 +178,7 @@ async fn handle_cli_connection(
         };
 
         // Send response back to CLI
-        if let Err(e) = protocol::write_response(&mut writer, &response).await {
+        if let Err(e) = protocol::write_response(&mut writer, &response, codec).await {
             error!("Failed to send CLI response: {}", e);
             break;
         }
@@ -121,15 +187,97 @@ async fn handle_cli_connection(
     Ok(())
 }
 
+/// Stream Bitwig notifications to a CLI client until it disconnects.
+///
+/// The CLI sends a single `bitwig_subscribe` request; we tell Bitwig to emit
+/// the requested categories and then write each pushed notification as its own
+/// frame. The loop selects between the next notification and the read half of
+/// the socket: when the client hangs up, the read resolves to EOF (or an error)
+/// and we tear the subscription down promptly rather than leaking the task and
+/// its broadcast receiver until some future event happens to fail a write.
+async fn stream_subscription<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    bitwig: &BitwigManager,
+    request: &RpcRequest,
+    codec: Codec,
+) where
+    R: tokio::io::AsyncReadExt + Unpin,
+    W: tokio::io::AsyncWriteExt + Unpin,
+{
+    let mut events = bitwig.subscribe();
+
+    // Relay the control call so the extension knows which categories to emit.
+    // Refcounted, so this never disturbs another client's active stream.
+    if let Err(e) = bitwig.subscribe_categories(&request.params).await {
+        let response = RpcResponse::error(request.id.clone(), -32603, e.to_string());
+        let _ = protocol::write_response(writer, &response, codec).await;
+        return;
+    }
+
+    // Acknowledge the subscription before the event stream begins.
+    let ack = RpcResponse::success(request.id.clone(), serde_json::json!({ "subscribed": true }));
+    if protocol::write_response(writer, &ack, codec).await.is_err() {
+        bitwig.unsubscribe_categories(&request.params).await;
+        return;
+    }
+
+    // A streaming client sends nothing more; any readable bytes are spurious,
+    // so we only care whether the read resolves at all — EOF or error means the
+    // client is gone.
+    let mut drain = [0u8; 256];
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(notification) => {
+                    if protocol::write_notification(writer, &notification, codec)
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                    error!("CLI subscription lagged, dropped {} events", n);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            },
+            read = reader.read(&mut drain) => match read {
+                Ok(0) | Err(_) => {
+                    info!("CLI subscription client disconnected");
+                    break;
+                }
+                // Unexpected inbound bytes during streaming; ignore and keep going.
+                Ok(_) => {}
+            },
+        }
+    }
+
+    bitwig.unsubscribe_categories(&request.params).await;
+}
+
 /// Listen for CLI connections
-pub async fn cli_listener(port: u16, bitwig: BitwigManager) -> Result<()> {
-    let listener = TcpListener::bind(format!("127.0.0.1:{}", port)).await?;
-    info!("CLI listener started on port {}", port);
+///
+/// Stops accepting new connections once `shutdown` flips to `true`.
+pub async fn cli_listener(
+    mut listener: Listener,
+    bitwig: BitwigManager,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) -> Result<()> {
+    info!("CLI listener started");
 
     loop {
-        match listener.accept().await {
-            Ok((stream, addr)) => {
-                info!("CLI connected from {}", addr);
+        let accepted = tokio::select! {
+            _ = shutdown.changed() => {
+                info!("CLI listener shutting down");
+                return Ok(());
+            }
+            accepted = listener.accept() => accepted,
+        };
+
+        match accepted {
+            Ok(stream) => {
+                info!("CLI connected");
 
                 let bitwig = bitwig.clone();
                 tokio::spawn(async move {
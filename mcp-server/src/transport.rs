@@ -0,0 +1,271 @@
+//! Local transport abstraction
+//!
+//! Both listeners can run over loopback TCP or over a local-IPC channel — a
+//! Unix domain socket on Unix, a named pipe on Windows — selected with the
+//! `GROOVE_TRANSPORT` environment variable (`tcp` | `ipc`, default `tcp`). IPC
+//! is permission-controlled by the filesystem and needs no port coordination;
+//! the Unix socket lives inside an owner-only `0700` directory so no other
+//! local user can reach it even during the window before its own mode is set.
+//!
+//! The framing and dispatch code in [`crate::protocol`] and [`crate::bitwig`]
+//! is generic over [`tokio::io::AsyncRead`]/[`AsyncWrite`], so both listeners
+//! yield a single [`Stream`] type and stay transport-agnostic.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use anyhow::{anyhow, Result};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+use tracing::info;
+
+/// Which transport the listeners expose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    Tcp,
+    Ipc,
+}
+
+impl Transport {
+    /// Read the transport from `GROOVE_TRANSPORT`, defaulting to TCP.
+    pub fn from_env() -> Self {
+        match std::env::var("GROOVE_TRANSPORT").ok().as_deref() {
+            Some("ipc") => Transport::Ipc,
+            _ => Transport::Tcp,
+        }
+    }
+}
+
+/// Where a listener binds: a loopback TCP port or a local-IPC address.
+#[derive(Debug, Clone)]
+pub enum Endpoint {
+    Tcp(u16),
+    Ipc(String),
+}
+
+/// An accepted connection, regardless of transport.
+pub enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+    #[cfg(windows)]
+    Pipe(tokio::net::windows::named_pipe::NamedPipeServer),
+}
+
+impl Stream {
+    /// Disable Nagle's algorithm on TCP; a no-op for IPC transports.
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        match self {
+            Stream::Tcp(s) => s.set_nodelay(nodelay),
+            #[cfg(unix)]
+            Stream::Unix(_) => Ok(()),
+            #[cfg(windows)]
+            Stream::Pipe(_) => Ok(()),
+        }
+    }
+}
+
+impl AsyncRead for Stream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Tcp(s) => Pin::new(s).poll_read(cx, buf),
+            #[cfg(unix)]
+            Stream::Unix(s) => Pin::new(s).poll_read(cx, buf),
+            #[cfg(windows)]
+            Stream::Pipe(s) => Pin::new(s).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Stream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Stream::Tcp(s) => Pin::new(s).poll_write(cx, buf),
+            #[cfg(unix)]
+            Stream::Unix(s) => Pin::new(s).poll_write(cx, buf),
+            #[cfg(windows)]
+            Stream::Pipe(s) => Pin::new(s).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Tcp(s) => Pin::new(s).poll_flush(cx),
+            #[cfg(unix)]
+            Stream::Unix(s) => Pin::new(s).poll_flush(cx),
+            #[cfg(windows)]
+            Stream::Pipe(s) => Pin::new(s).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Tcp(s) => Pin::new(s).poll_shutdown(cx),
+            #[cfg(unix)]
+            Stream::Unix(s) => Pin::new(s).poll_shutdown(cx),
+            #[cfg(windows)]
+            Stream::Pipe(s) => Pin::new(s).poll_shutdown(cx),
+        }
+    }
+}
+
+/// A transport-agnostic listener that yields [`Stream`]s.
+pub enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+    #[cfg(windows)]
+    Pipe {
+        addr: String,
+        pending: tokio::net::windows::named_pipe::NamedPipeServer,
+    },
+}
+
+impl Listener {
+    /// Bind the listener described by `endpoint`.
+    pub async fn bind(endpoint: &Endpoint) -> Result<Self> {
+        match endpoint {
+            Endpoint::Tcp(port) => {
+                let listener = TcpListener::bind(format!("127.0.0.1:{}", port)).await?;
+                info!("Bound TCP listener on 127.0.0.1:{}", port);
+                Ok(Listener::Tcp(listener))
+            }
+            Endpoint::Ipc(addr) => Self::bind_ipc(addr),
+        }
+    }
+
+    #[cfg(unix)]
+    fn bind_ipc(addr: &str) -> Result<Self> {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Confine the socket to an owner-only (0700) directory instead of
+        // binding straight into shared /tmp and relaxing-then-tightening the
+        // socket's own mode. A 0700 parent removes the umask-dependent window
+        // in which a freshly bound socket is world-connectable before
+        // `set_permissions` runs, and narrows the symlink/pre-creation races of
+        // `remove_file` + bind (the caller gives us a per-user parent path so a
+        // squatter cannot sit on a predictable shared name).
+        let path = std::path::Path::new(addr);
+        let dir = path
+            .parent()
+            .ok_or_else(|| anyhow!("IPC address {} has no parent directory", addr))?;
+        Self::ensure_private_dir(dir)?;
+
+        // The parent is now ours and 0700, so nothing can have pre-staged a
+        // symlink here; a stale socket from a previous run is all we expect.
+        if let Err(e) = std::fs::remove_file(addr) {
+            if e.kind() != io::ErrorKind::NotFound {
+                return Err(e.into());
+            }
+        }
+        let listener = UnixListener::bind(addr)?;
+
+        // Belt and braces: the 0700 directory already makes the socket
+        // unreachable to other users, but keep the socket node owner-only too.
+        std::fs::set_permissions(addr, std::fs::Permissions::from_mode(0o600))?;
+
+        info!(
+            "Bound Unix socket listener on {} (dir mode 0700, socket mode 0600)",
+            addr
+        );
+        Ok(Listener::Unix(listener))
+    }
+
+    #[cfg(windows)]
+    fn bind_ipc(addr: &str) -> Result<Self> {
+        use tokio::net::windows::named_pipe::ServerOptions;
+        let pending = ServerOptions::new()
+            .first_pipe_instance(true)
+            .create(addr)?;
+        info!("Bound named-pipe listener on {}", addr);
+        Ok(Listener::Pipe {
+            addr: addr.to_string(),
+            pending,
+        })
+    }
+
+    #[cfg(unix)]
+    /// Create `dir` as an owner-only `0700` directory, or adopt an existing one
+    /// only if it is safe to.
+    ///
+    /// The common case is a fresh `mkdir` with mode `0700` set atomically, so
+    /// there is no umask-widened window between creation and a follow-up chmod.
+    /// If the path already exists we refuse a symlink (the classic /tmp
+    /// redirect) or any non-directory, then re-assert `0700`; a directory owned
+    /// by another user makes that chmod fail, which we surface as a clear
+    /// refusal rather than trusting a directory we do not control. This narrows
+    /// — but, on a shared /tmp, cannot fully close — the check-then-bind race,
+    /// which is why callers prefer a per-user parent path.
+    fn ensure_private_dir(dir: &std::path::Path) -> Result<()> {
+        use std::fs::DirBuilder;
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+        // Make sure the parent exists (e.g. an XDG_RUNTIME_DIR base that is
+        // configured but not yet materialized) so the atomic leaf mkdir below
+        // is the only step that decides the private directory's mode.
+        if let Some(parent) = dir.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        match DirBuilder::new().mode(0o700).create(dir) {
+            Ok(()) => return Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let meta = std::fs::symlink_metadata(dir)?;
+        if meta.file_type().is_symlink() {
+            return Err(anyhow!("refusing to use {}: it is a symlink", dir.display()));
+        }
+        if !meta.is_dir() {
+            return Err(anyhow!("{} exists and is not a directory", dir.display()));
+        }
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)).map_err(|e| {
+            anyhow!(
+                "refusing to use {}: cannot enforce owner-only 0700 ({})",
+                dir.display(),
+                e
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Accept the next connection.
+    pub async fn accept(&mut self) -> Result<Stream> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, addr) = listener.accept().await?;
+                info!("Accepted TCP connection from {}", addr);
+                Ok(Stream::Tcp(stream))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener) => {
+                let (stream, _addr) = listener.accept().await?;
+                info!("Accepted Unix socket connection");
+                Ok(Stream::Unix(stream))
+            }
+            #[cfg(windows)]
+            Listener::Pipe { addr, pending } => {
+                use tokio::net::windows::named_pipe::ServerOptions;
+                // Wait for a client on the current instance, then stand up the
+                // next one so the following `accept` has something to hand out.
+                pending.connect().await?;
+                let next = ServerOptions::new().create(&*addr)?;
+                let connected = std::mem::replace(pending, next);
+                info!("Accepted named-pipe connection");
+                Ok(Stream::Pipe(connected))
+            }
+        }
+    }
+}